@@ -1,15 +1,13 @@
 use nix::sys::signal::Signal;
 use parking_lot::Mutex;
 use reqwest::Url;
-use signal_hook::consts::{SIGINT, SIGTERM};
 use std::{
     collections::VecDeque,
-    io::{Read, Write},
-    process::{Child, Command, ExitStatus, Stdio},
+    process::Stdio,
     str::FromStr,
     sync::{
         atomic::{AtomicBool, Ordering},
-        mpsc, Arc,
+        Arc,
     },
     time::{Duration, Instant},
 };
@@ -18,7 +16,19 @@ use anyhow::{Context, Result};
 
 use clap::{arg, Parser};
 
-use crate::{line_helper::LineHelper, slack::SlackApp};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    process::Command,
+    signal::unix::{signal, Signal as UnixSignal, SignalKind},
+    sync::{mpsc, Notify},
+};
+
+use crate::{
+    discord::DiscordApp,
+    line_helper::{self, LineHelper},
+    notifier::{AppDetail, Notifier},
+    slack::SlackApp,
+};
 
 #[derive(Parser)]
 pub(crate) struct Cli {
@@ -26,8 +36,21 @@ pub(crate) struct Cli {
     #[arg(long)]
     pub(crate) task_output_timeout: Option<u64>,
     /// Slack Webhook for notification
-    #[arg(long, value_parser(Url::from_str), env = "HEALTH_CHECK_SLACK_WEBHOOK")]
-    pub(crate) slack_webhook: Url,
+    #[arg(
+        long,
+        value_parser(Url::from_str),
+        env = "HEALTH_CHECK_SLACK_WEBHOOK",
+        required = false
+    )]
+    pub(crate) slack_webhook: Option<Url>,
+    /// Discord Webhook for notification
+    #[arg(
+        long,
+        value_parser(Url::from_str),
+        env = "HEALTH_CHECK_DISCORD_WEBHOOK",
+        required = false
+    )]
+    pub(crate) discord_webhook: Option<Url>,
     /// Application description
     #[arg(long)]
     pub(crate) app_description: String,
@@ -52,17 +75,146 @@ pub(crate) struct Cli {
     /// How many lines of output should we store for error messages?
     #[arg(long, default_value_t = 50, env = "HEALTH_CHECK_OUTPUT_LINES")]
     pub(crate) output_lines: usize,
+    /// URL to periodically GET as an active health probe. When both this and
+    /// `health_tcp` are set, the HTTP probe wins.
+    #[arg(long, value_parser(Url::from_str), required = false)]
+    pub(crate) health_url: Option<Url>,
+    /// `host:port` to periodically open a TCP connection to as an active
+    /// health probe
+    #[arg(long, required = false)]
+    pub(crate) health_tcp: Option<String>,
+    /// Seconds between active health probes
+    #[arg(long, default_value_t = 10)]
+    pub(crate) health_interval: u64,
+    /// Seconds to wait for an active health probe to respond
+    #[arg(long, default_value_t = 5)]
+    pub(crate) health_timeout: u64,
+    /// Seconds to wait after spawning the child before starting active
+    /// health probes, so a slow-starting server isn't probed too early
+    #[arg(long, default_value_t = 0)]
+    pub(crate) health_start_grace: u64,
+    /// Consecutive active health probe failures tolerated before treating
+    /// the child as unhealthy
+    #[arg(long, default_value_t = 3)]
+    pub(crate) health_failures: u32,
+    /// Restart the command instead of giving up when it exits or is killed
+    /// by the deadlock/health-check watchdogs
+    #[arg(long)]
+    pub(crate) restart: bool,
+    /// Maximum number of restarts tolerated before giving up and sending the
+    /// configured notifications. Only used with `--restart`.
+    #[arg(long, default_value_t = 5)]
+    pub(crate) max_restarts: u32,
+    /// Initial backoff before the first restart attempt, in seconds
+    #[arg(long, default_value_t = 1)]
+    pub(crate) restart_backoff_initial: u64,
+    /// Maximum backoff between restart attempts, in seconds. The backoff
+    /// doubles after every restart until it hits this cap.
+    #[arg(long, default_value_t = 60)]
+    pub(crate) restart_backoff_max: u64,
+    /// How long the child must stay up, in seconds, before a subsequent exit
+    /// resets the restart count and backoff back to their initial values
+    #[arg(long, default_value_t = 60)]
+    pub(crate) restart_stability_threshold: u64,
+    /// Parse child output as JSON Lines and alert when a line's `level`
+    /// field meets or exceeds `--alert-level`, even if the process hasn't
+    /// crashed
+    #[arg(long)]
+    pub(crate) json_logs: bool,
+    /// Minimum structured log severity that triggers an alert. Only used
+    /// with `--json-logs`.
+    #[arg(long, value_enum, default_value_t = AlertLevel::Error)]
+    pub(crate) alert_level: AlertLevel,
+    /// Seconds to debounce a burst of alerting log lines into a single
+    /// notification. Only used with `--json-logs`.
+    #[arg(long, default_value_t = 5)]
+    pub(crate) alert_debounce: u64,
+}
+
+/// Severity threshold for `--alert-level`. Ordered so that `Error` is
+/// considered more severe than `Warn`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub(crate) enum AlertLevel {
+    Warn,
+    Error,
 }
 
 #[derive(Debug)]
 enum MainMessage {
     Error(anyhow::Error),
     DeadlockDetected,
-    ChildExited(ExitStatus),
+    HealthCheckFailed { detail: String },
+    LogAlert { line: String },
 }
 
+enum HealthProbeTarget {
+    Http(Url),
+    Tcp(String),
+}
+
+/// Shared state for debouncing structured-log alerts: qualifying lines pile
+/// up in `state.pending` until the debounce timer (only ever one in flight,
+/// guarded by `state.timer_running`) flushes them as a single
+/// `MainMessage::LogAlert`. Both fields live behind the same lock so a line
+/// can never be pushed into a batch that's already been taken for flushing.
 #[derive(Clone)]
-struct SendMainMessage(mpsc::Sender<MainMessage>);
+struct LogAlertConfig {
+    alert_level: AlertLevel,
+    debounce: Duration,
+    state: Arc<Mutex<LogAlertState>>,
+}
+
+#[derive(Default)]
+struct LogAlertState {
+    pending: Vec<String>,
+    timer_running: bool,
+}
+
+/// If `line` parses as a JSON object with a recognized `level` field, return
+/// the severity it maps to.
+fn json_log_level(line: &str) -> Option<AlertLevel> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    let level = value.get("level")?.as_str()?;
+    match level.to_ascii_lowercase().as_str() {
+        "warn" | "warning" => Some(AlertLevel::Warn),
+        "error" | "err" | "fatal" | "critical" => Some(AlertLevel::Error),
+        _ => None,
+    }
+}
+
+/// Queue `line` as a triggering log alert, starting the debounce timer if one
+/// isn't already in flight.
+fn queue_log_alert(send: &SendMainMessage, config: &LogAlertConfig, line: String) {
+    let mut state = config.state.lock();
+    state.pending.push(line);
+
+    if !state.timer_running {
+        state.timer_running = true;
+        drop(state);
+
+        let send = send.clone();
+        let config = config.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(config.debounce).await;
+            // Take the pending lines and clear the flag in one locked step,
+            // so a line pushed concurrently can't land in a batch that's
+            // already been taken without anyone left to flush it.
+            let lines = {
+                let mut state = config.state.lock();
+                state.timer_running = false;
+                std::mem::take(&mut state.pending)
+            };
+            if !lines.is_empty() {
+                send.send(MainMessage::LogAlert {
+                    line: lines.join("\n"),
+                });
+            }
+        });
+    }
+}
+
+#[derive(Clone)]
+struct SendMainMessage(mpsc::UnboundedSender<MainMessage>);
 
 impl SendMainMessage {
     fn send(&self, msg: MainMessage) {
@@ -81,139 +233,355 @@ enum StdType {
     Stderr,
 }
 
-impl Cli {
-    pub(crate) fn run(self) -> Result<()> {
-        let mut command = Command::new(&self.command);
-        command.args(&self.args[..]);
+/// The outcome of a single run of the supervised command: either it's fine
+/// to exit cleanly, or it failed along with whatever recent output context
+/// we managed to capture.
+struct Supervised {
+    result: Result<()>,
+    recent_output_prefix: Option<String>,
+    recent_output: Arc<Mutex<VecDeque<String>>>,
+}
 
-        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+/// The child currently being supervised, as far as the long-lived signal
+/// handler is concerned.
+struct SignalTarget {
+    pid: nix::unistd::Pid,
+    child_was_killed: Arc<AtomicBool>,
+}
 
-        let mut child = command
-            .spawn()
-            .context(format!("Failed to spawn {}", self.command))?;
+/// `None` whenever no child is currently spawned (between restarts, or
+/// before the first spawn/after the last one settles).
+type SharedSignalTarget = Arc<Mutex<Option<SignalTarget>>>;
 
-        let (send, recv) = mpsc::channel::<MainMessage>();
-        let send = SendMainMessage(send);
-        let max_recent_output = self.output_lines;
-        let recent_output = Arc::new(Mutex::new(VecDeque::with_capacity(max_recent_output)));
+impl Cli {
+    pub(crate) async fn run(self) -> Result<()> {
+        // Registered once for the whole process lifetime: `signal()`
+        // installs the sigaction for SIGTERM/SIGINT and never uninstalls it,
+        // even if the listening task stops running. Scoping this per
+        // restart attempt would leave Ctrl-C unobservable while sleeping
+        // between restarts, so it's set up here instead and just retargeted
+        // at whichever child is currently running.
+        let mut sigterm = signal(SignalKind::terminate()).context("Registering SIGTERM handler")?;
+        let mut sigint = signal(SignalKind::interrupt()).context("Registering SIGINT handler")?;
+        let signal_target: SharedSignalTarget = Arc::new(Mutex::new(None));
+        // Fired by `handle_signals` when a signal arrives with no child to
+        // forward it to (i.e. while waiting out the restart backoff), so
+        // that window doesn't leave Ctrl-C/SIGTERM with nothing to observe
+        // it.
+        let shutdown = Arc::new(Notify::new());
+        tokio::spawn({
+            let signal_target = signal_target.clone();
+            let shutdown = shutdown.clone();
+            async move { handle_signals(&mut sigterm, &mut sigint, signal_target, shutdown).await }
+        });
 
-        // Always capture output so we can keep recent output available for error messages.
-        let last_output = Arc::new(Mutex::new(Instant::now()));
-        {
-            let child_stdout = child.stdout.take().context("child stdout is None")?;
-            let child_stderr = child.stderr.take().context("child stderr is None")?;
-            let send_clone = send.clone();
-            let last_output_clone = last_output.clone();
-            let recent_output_clone = recent_output.clone();
-            std::thread::spawn(move || {
-                process_std_handle(
-                    child_stdout,
-                    send_clone,
-                    StdType::Stdout,
-                    last_output_clone,
-                    recent_output_clone,
-                    max_recent_output,
-                )
-            });
-            let send_clone = send.clone();
-            let last_output_clone = last_output.clone();
-            let recent_output_clone = recent_output.clone();
-            std::thread::spawn(move || {
-                process_std_handle(
-                    child_stderr,
-                    send_clone,
-                    StdType::Stderr,
-                    last_output_clone,
-                    recent_output_clone,
-                    max_recent_output,
-                )
-            });
+        if !self.restart {
+            let supervised = spawn_and_supervise(&self, &signal_target).await?;
+            return self.finish(supervised).await;
         }
 
-        if let Some(task_output_timeout) = self.task_output_timeout {
-            let send_clone = send.clone();
-            std::thread::spawn(move || {
-                detect_deadlock(
-                    last_output,
-                    send_clone,
-                    Duration::from_secs(task_output_timeout),
-                )
-            });
-        }
+        let mut restart_count = 0u32;
+        let mut backoff = Duration::from_secs(self.restart_backoff_initial);
+        let backoff_max = Duration::from_secs(self.restart_backoff_max);
+        let stability_threshold = Duration::from_secs(self.restart_stability_threshold);
 
-        let child_pid = i32::try_from(child.id())?;
-        static CHILD_WAS_KILLED: AtomicBool = AtomicBool::new(false);
-        std::thread::spawn({
-            let send = send.clone();
-            move || {
-                handle_signals(
-                    send,
-                    nix::unistd::Pid::from_raw(child_pid),
-                    &CHILD_WAS_KILLED,
-                )
-            }
-        });
+        loop {
+            let started_at = Instant::now();
+            let supervised = spawn_and_supervise(&self, &signal_target).await?;
+            match supervised.result {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    if started_at.elapsed() >= stability_threshold {
+                        restart_count = 0;
+                        backoff = Duration::from_secs(self.restart_backoff_initial);
+                    }
+
+                    if restart_count >= self.max_restarts {
+                        return self
+                            .finish(Supervised {
+                                result: Err(e),
+                                ..supervised
+                            })
+                            .await;
+                    }
 
-        std::thread::spawn(|| watch_child(send, child));
-
-        let msg = recv.recv();
-        // Drop the recv immediately, just a minor optimization to avoid
-        // additional messages building up in the queue where we won't see them.
-        std::mem::drop(recv);
-        let res = match msg {
-            Ok(msg) => match msg {
-                MainMessage::Error(e) => Err(e),
-                MainMessage::DeadlockDetected => Err(anyhow::anyhow!(
-                    "Potential deadlock detected, too long without output from child process"
-                )),
-                MainMessage::ChildExited(exit_status) => {
-                    if self.can_exit && exit_status.success()
-                        || CHILD_WAS_KILLED.load(Ordering::SeqCst)
-                    {
-                        eprintln!("Child exited, treating as a success case");
-                        Ok(())
-                    } else {
-                        Err(anyhow::anyhow!("Child exited with status {exit_status}"))
+                    restart_count += 1;
+                    eprintln!(
+                        "Command exited (restart {restart_count}/{}), retrying in {backoff:?}: {e:?}",
+                        self.max_restarts
+                    );
+                    tokio::select! {
+                        _ = tokio::time::sleep(backoff) => {}
+                        _ = shutdown.notified() => {
+                            eprintln!("Received signal while waiting to restart, shutting down");
+                            return Ok(());
+                        }
                     }
+                    backoff = std::cmp::min(backoff * 2, backoff_max);
                 }
-            },
-            Err(_) => Err(anyhow::anyhow!(
-                "Impossible, all send channels have been closed"
+            }
+        }
+    }
+
+    /// Send the configured notifications (if the run failed) and return the
+    /// final result.
+    async fn finish(&self, supervised: Supervised) -> Result<()> {
+        if let Err(e) = &supervised.result {
+            let mut msg = String::new();
+            if let Some(prefix) = &supervised.recent_output_prefix {
+                msg.push_str(prefix);
+                msg.push('\n');
+            }
+            for line in &*supervised.recent_output.lock() {
+                msg.push_str(line);
+                msg.push('\n');
+            }
+            notify_all(self, e, &msg).await;
+        }
+        supervised.result
+    }
+}
+
+/// Build the notifiers configured on the CLI (zero or more of Slack/Discord)
+/// and fan the notification out to all of them, logging per-sink failures
+/// instead of aborting.
+async fn notify_all(cli: &Cli, err: &anyhow::Error, recent_output: &str) {
+    let app_info = AppDetail {
+        message: cli.notification_context.clone(),
+        description: cli.app_description.clone(),
+        version: cli.app_version.clone(),
+        image_url: cli.image_url.clone(),
+    };
+    let mut notifiers: Vec<(&str, Box<dyn Notifier>)> = vec![];
+    if let Some(webhook) = &cli.slack_webhook {
+        notifiers.push((
+            "Slack",
+            Box::new(SlackApp::new(
+                webhook.clone(),
+                app_info.message.clone(),
+                app_info.description.clone(),
+                app_info.version.clone(),
+                app_info.image_url.clone(),
             )),
-        };
+        ));
+    }
+    if let Some(webhook) = &cli.discord_webhook {
+        notifiers.push((
+            "Discord",
+            Box::new(DiscordApp::new(
+                webhook.clone(),
+                app_info.message.clone(),
+                app_info.description.clone(),
+                app_info.version.clone(),
+                app_info.image_url.clone(),
+            )),
+        ));
+    }
 
-        match res {
-            Ok(()) => Ok(()),
-            Err(e) => {
-                let slack_app = SlackApp::new(
-                    self.slack_webhook,
-                    self.notification_context,
-                    self.app_description,
-                    self.app_version,
-                    self.image_url,
-                );
-                let mut msg = String::new();
-                for line in &*recent_output.lock() {
-                    msg.push_str(line);
-                    msg.push('\n');
-                }
-                let result = slack_app.send_notification(&e, &msg);
-                if let Err(err) = result {
-                    eprintln!("Slack notification failed: {err:?}");
+    for (name, notifier) in notifiers {
+        if let Err(send_err) = notifier.send_notification(err, recent_output).await {
+            eprintln!("{name} notification failed: {send_err:?}");
+        }
+    }
+}
+
+/// Spawn the command, wire up the stdout/stderr readers, deadlock detector
+/// and health probe against it, point the process-wide signal handler at it,
+/// and race the child's exit against a single terminal `MainMessage` to
+/// decide whether this run succeeded or failed.
+///
+/// Every call creates fresh `recent_output`/`last_output` state and a fresh
+/// set of tasks, so this is the unit of work repeated by the restart loop
+/// in `Cli::run`.
+async fn spawn_and_supervise(cli: &Cli, signal_target: &SharedSignalTarget) -> Result<Supervised> {
+    let mut command = Command::new(&cli.command);
+    command.args(&cli.args[..]);
+
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    let mut child = command
+        .spawn()
+        .context(format!("Failed to spawn {}", cli.command))?;
+
+    let (send, mut recv) = mpsc::unbounded_channel::<MainMessage>();
+    let send = SendMainMessage(send);
+    let max_recent_output = cli.output_lines;
+    let recent_output = Arc::new(Mutex::new(VecDeque::with_capacity(max_recent_output)));
+
+    let log_alert_config = cli.json_logs.then(|| LogAlertConfig {
+        alert_level: cli.alert_level,
+        debounce: Duration::from_secs(cli.alert_debounce),
+        state: Arc::new(Mutex::new(LogAlertState::default())),
+    });
+
+    // Always capture output so we can keep recent output available for error messages.
+    let last_output = Arc::new(Mutex::new(Instant::now()));
+    {
+        let child_stdout = child.stdout.take().context("child stdout is None")?;
+        let child_stderr = child.stderr.take().context("child stderr is None")?;
+        let send_clone = send.clone();
+        let last_output_clone = last_output.clone();
+        let recent_output_clone = recent_output.clone();
+        let log_alert_clone = log_alert_config.clone();
+        tokio::spawn(process_std_handle(
+            child_stdout,
+            send_clone,
+            StdType::Stdout,
+            last_output_clone,
+            recent_output_clone,
+            max_recent_output,
+            log_alert_clone,
+        ));
+        let send_clone = send.clone();
+        let last_output_clone = last_output.clone();
+        let recent_output_clone = recent_output.clone();
+        let log_alert_clone = log_alert_config.clone();
+        tokio::spawn(process_std_handle(
+            child_stderr,
+            send_clone,
+            StdType::Stderr,
+            last_output_clone,
+            recent_output_clone,
+            max_recent_output,
+            log_alert_clone,
+        ));
+    }
+
+    if let Some(task_output_timeout) = cli.task_output_timeout {
+        let send_clone = send.clone();
+        tokio::spawn(detect_deadlock(
+            last_output,
+            send_clone,
+            Duration::from_secs(task_output_timeout),
+        ));
+    }
+
+    let health_probe_target = match (&cli.health_url, &cli.health_tcp) {
+        (Some(url), _) => Some(HealthProbeTarget::Http(url.clone())),
+        (None, Some(addr)) => Some(HealthProbeTarget::Tcp(addr.clone())),
+        (None, None) => None,
+    };
+    if let Some(target) = health_probe_target {
+        let send_clone = send.clone();
+        let health_interval = Duration::from_secs(cli.health_interval);
+        let health_timeout = Duration::from_secs(cli.health_timeout);
+        let health_start_grace = Duration::from_secs(cli.health_start_grace);
+        let health_failures = cli.health_failures;
+        tokio::spawn(run_health_probe(
+            send_clone,
+            target,
+            health_interval,
+            health_timeout,
+            health_start_grace,
+            health_failures,
+        ));
+    }
+
+    let child_id = child
+        .id()
+        .context("Child has no pid, has it already exited?")?;
+    let child_pid = i32::try_from(child_id)?;
+    let child_was_killed = Arc::new(AtomicBool::new(false));
+    // Point the process-wide signal handler at this child. Cleared again
+    // once this run settles, so a signal arriving between runs isn't sent to
+    // a pid that's already gone.
+    *signal_target.lock() = Some(SignalTarget {
+        pid: nix::unistd::Pid::from_raw(child_pid),
+        child_was_killed: child_was_killed.clone(),
+    });
+
+    // A LogAlert is informational, not terminal: keep looping until we see a
+    // message (or the child's own exit) that actually decides this run's
+    // outcome.
+    let mut recent_output_prefix: Option<String> = None;
+    let result = loop {
+        tokio::select! {
+            exit_status = child.wait() => {
+                let exit_status = exit_status.context("Unable to wait for child process to exit")?;
+                break if cli.can_exit && exit_status.success()
+                    || child_was_killed.load(Ordering::SeqCst)
+                {
+                    eprintln!("Child exited, treating as a success case");
+                    Ok(())
+                } else {
+                    Err(anyhow::anyhow!("Child exited with status {exit_status}"))
+                };
+            }
+            msg = recv.recv() => {
+                match msg {
+                    Some(MainMessage::LogAlert { line }) => {
+                        let mut msg = line.clone();
+                        msg.push('\n');
+                        for recent_line in &*recent_output.lock() {
+                            msg.push_str(recent_line);
+                            msg.push('\n');
+                        }
+                        notify_all(
+                            cli,
+                            &anyhow::anyhow!(
+                                "Structured log alert (threshold: {:?})",
+                                cli.alert_level
+                            ),
+                            &msg,
+                        )
+                        .await;
+                    }
+                    Some(MainMessage::Error(e)) => break Err(e),
+                    Some(MainMessage::DeadlockDetected) => {
+                        if let Err(e) = nix::sys::signal::kill(
+                            nix::unistd::Pid::from_raw(child_pid),
+                            Signal::SIGKILL,
+                        )
+                        .context("Unable to kill child after deadlock detection")
+                        {
+                            eprintln!("{e:?}");
+                        }
+                        break Err(anyhow::anyhow!(
+                            "Potential deadlock detected, too long without output from child process"
+                        ))
+                    }
+                    Some(MainMessage::HealthCheckFailed { detail }) => {
+                        if let Err(e) = nix::sys::signal::kill(
+                            nix::unistd::Pid::from_raw(child_pid),
+                            Signal::SIGKILL,
+                        )
+                        .context("Unable to kill child after health probe failure")
+                        {
+                            eprintln!("{e:?}");
+                        }
+                        recent_output_prefix = Some(detail.clone());
+                        break Err(anyhow::anyhow!("Active health probe failed: {detail}"));
+                    }
+                    None => {
+                        break Err(anyhow::anyhow!(
+                            "Impossible, all send channels have been closed"
+                        ))
+                    }
                 }
-                Err(e)
             }
         }
-    }
+    };
+    // Drop the recv immediately, just a minor optimization to avoid
+    // additional messages building up in the queue where we won't see them.
+    std::mem::drop(recv);
+    *signal_target.lock() = None;
+
+    Ok(Supervised {
+        result,
+        recent_output_prefix,
+        recent_output,
+    })
 }
 
-fn process_std_handle(
-    mut reader: impl Read,
+#[allow(clippy::too_many_arguments)]
+async fn process_std_handle(
+    mut reader: impl tokio::io::AsyncRead + Unpin,
     send: SendMainMessage,
     std_type: StdType,
     last_output: Arc<Mutex<Instant>>,
     recent_output: Arc<Mutex<VecDeque<String>>>,
     max_recent_output: usize,
+    log_alert: Option<LogAlertConfig>,
 ) {
     let mut buffer = [0u8; 4096];
     let mut line_helper = LineHelper::new();
@@ -221,6 +589,7 @@ fn process_std_handle(
     loop {
         match reader
             .read(&mut buffer)
+            .await
             .context("Unable to read from {std_type:?}")
         {
             Ok(size) => {
@@ -230,13 +599,13 @@ fn process_std_handle(
                 *last_output.lock() = Instant::now();
                 let buffer = &buffer[..size];
                 let res = match std_type {
-                    StdType::Stdout => std::io::stdout()
-                        .lock()
+                    StdType::Stdout => tokio::io::stdout()
                         .write_all(buffer)
+                        .await
                         .context("Unable to write to stdout"),
-                    StdType::Stderr => std::io::stderr()
-                        .lock()
+                    StdType::Stderr => tokio::io::stderr()
                         .write_all(buffer)
+                        .await
                         .context("Unable to write to stderr"),
                 };
                 if let Err(e) = res {
@@ -245,11 +614,19 @@ fn process_std_handle(
                 }
 
                 for line in line_helper.append(&buffer[..size]) {
+                    if let Some(log_alert) = &log_alert {
+                        if let Some(level) = json_log_level(&line) {
+                            if level >= log_alert.alert_level {
+                                queue_log_alert(&send, log_alert, line.clone());
+                            }
+                        }
+                    }
+
                     let mut guard = recent_output.lock();
                     if guard.len() >= max_recent_output {
                         guard.pop_front();
                     }
-                    guard.push_back(line);
+                    guard.push_back(line_helper::demangle_line(&line));
                 }
             }
             Err(e) => {
@@ -264,11 +641,11 @@ fn process_std_handle(
         if guard.len() >= max_recent_output {
             guard.pop_front();
         }
-        guard.push_back(line);
+        guard.push_back(line_helper::demangle_line(&line));
     }
 }
 
-fn detect_deadlock(
+async fn detect_deadlock(
     last_output_mutex: Arc<Mutex<Instant>>,
     send: SendMainMessage,
     task_output_timeout: Duration,
@@ -287,7 +664,7 @@ fn detect_deadlock(
         };
         match next_deadlock_detected.checked_duration_since(Instant::now()) {
             Some(to_sleep) => {
-                std::thread::sleep(to_sleep);
+                tokio::time::sleep(to_sleep).await;
             }
             None => {
                 send.send(MainMessage::DeadlockDetected);
@@ -297,46 +674,103 @@ fn detect_deadlock(
     }
 }
 
-fn watch_child(send: SendMainMessage, mut child: Child) {
-    match child
-        .wait()
-        .context("Unable to wait for child process to exit")
-    {
-        Ok(exit_status) => send.send(MainMessage::ChildExited(exit_status)),
-        Err(e) => send.send(MainMessage::Error(e)),
-    }
-}
-
-fn handle_signals(
+#[allow(clippy::too_many_arguments)]
+async fn run_health_probe(
     send: SendMainMessage,
-    child_pid: nix::unistd::Pid,
-    child_was_killed: &AtomicBool,
+    target: HealthProbeTarget,
+    interval: Duration,
+    timeout: Duration,
+    start_grace: Duration,
+    max_consecutive_failures: u32,
 ) {
-    let mut signals = match signal_hook::iterator::Signals::new([SIGTERM, SIGINT])
-        .context("Creating new Signals value")
-    {
-        Ok(signals) => signals,
-        Err(e) => {
-            send.send(MainMessage::Error(e));
-            return;
+    tokio::time::sleep(start_grace).await;
+
+    let mut consecutive_failures = 0u32;
+    loop {
+        let result = match &target {
+            HealthProbeTarget::Http(url) => probe_http(url, timeout).await,
+            HealthProbeTarget::Tcp(addr) => probe_tcp(addr, timeout).await,
+        };
+        match result {
+            Ok(()) => consecutive_failures = 0,
+            Err(e) => {
+                consecutive_failures += 1;
+                eprintln!(
+                    "Health probe failed ({consecutive_failures}/{max_consecutive_failures}): {e:?}"
+                );
+                if consecutive_failures >= max_consecutive_failures {
+                    send.send(MainMessage::HealthCheckFailed {
+                        detail: format!(
+                            "Health probe failed {consecutive_failures} consecutive times: {e}"
+                        ),
+                    });
+                    break;
+                }
+            }
         }
-    };
+        tokio::time::sleep(interval).await;
+    }
+}
 
-    for signal in signals.forever() {
-        match Signal::try_from(signal)
-            .with_context(|| format!("Unable to convert signal value for nix: {signal}"))
-        {
-            Ok(signal) => {
-                child_was_killed.store(true, Ordering::SeqCst);
-                if let Err(e) = nix::sys::signal::kill(child_pid, signal)
+async fn probe_http(url: &Url, timeout: Duration) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .context("Unable to build health probe HTTP client")?;
+    let response = client
+        .get(url.clone())
+        .send()
+        .await
+        .with_context(|| format!("Health probe GET {url} failed"))?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Health probe GET {url} returned status {}",
+            response.status()
+        ))
+    }
+}
+
+async fn probe_tcp(addr: &str, timeout: Duration) -> Result<()> {
+    tokio::time::timeout(timeout, tokio::net::TcpStream::connect(addr))
+        .await
+        .with_context(|| format!("TCP connect to {addr} timed out"))?
+        .with_context(|| format!("TCP connect to {addr} failed"))?;
+    Ok(())
+}
+
+/// Runs for the entire lifetime of `Cli::run`, across every restart attempt,
+/// since `tokio::signal::unix::signal` installs the SIGTERM/SIGINT sigaction
+/// once per process and has no way to uninstall it again. Forwards each
+/// signal to whichever child `signal_target` currently points at; when
+/// there's no child to forward to (e.g. waiting out the restart backoff),
+/// fires `shutdown` instead so the caller can stop rather than silently
+/// swallowing the signal.
+async fn handle_signals(
+    sigterm: &mut UnixSignal,
+    sigint: &mut UnixSignal,
+    signal_target: SharedSignalTarget,
+    shutdown: Arc<Notify>,
+) {
+    loop {
+        let signal = tokio::select! {
+            _ = sigterm.recv() => Signal::SIGTERM,
+            _ = sigint.recv() => Signal::SIGINT,
+        };
+        match &*signal_target.lock() {
+            Some(target) => {
+                target.child_was_killed.store(true, Ordering::SeqCst);
+                if let Err(e) = nix::sys::signal::kill(target.pid, signal)
                     .context("Unable to send signal to child process")
                 {
-                    send.send(MainMessage::Error(e));
+                    eprintln!("{e:?}");
                 }
             }
-            Err(e) => {
-                send.send(MainMessage::Error(e));
+            None => {
+                eprintln!("Received signal but no child is currently supervised; shutting down");
+                shutdown.notify_one();
             }
-        };
+        }
     }
 }
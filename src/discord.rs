@@ -0,0 +1,85 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use reqwest::Url;
+
+use crate::notifier::{readable_image_id, AppDetail, Notifier};
+
+pub(crate) struct DiscordApp {
+    webhook: Url,
+    app_info: AppDetail,
+}
+
+impl DiscordApp {
+    pub(crate) fn new(
+        webhook: Url,
+        message: String,
+        description: String,
+        version: String,
+        image_url: Option<String>,
+    ) -> DiscordApp {
+        DiscordApp {
+            webhook,
+            app_info: AppDetail {
+                message,
+                description,
+                version,
+                image_url,
+            },
+        }
+    }
+
+    fn compute_description(&self) -> String {
+        let version = readable_image_id(&self.app_info.version);
+        let message = self.app_info.message.replace("\\n", "\n");
+        format!(
+            "{} \n**Application**: {} \n**Version**: {}",
+            message, self.app_info.description, version
+        )
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordApp {
+    async fn send_notification(&self, err: &anyhow::Error, recent_output: &str) -> Result<()> {
+        let mut embed = serde_json::json!(
+        {
+            "title": err.to_string(),
+            "description": self.compute_description(),
+        });
+        if let Some(image_url) = &self.app_info.image_url {
+            let object = embed
+                .as_object_mut()
+                .context("Embed JSON value should be an object")?;
+            object.insert("image".to_owned(), serde_json::json!({ "url": image_url }));
+        }
+        if !recent_output.is_empty() {
+            let object = embed
+                .as_object_mut()
+                .context("Embed JSON value should be an object")?;
+            object.insert(
+                "fields".to_owned(),
+                serde_json::json!([
+                    {
+                        "name": "Recent output",
+                        "value": format!("```{recent_output}```"),
+                    }
+                ]),
+            );
+        }
+        let value = serde_json::json!({ "embeds": [embed] });
+        let client = reqwest::Client::new();
+        let response = client
+            .post(self.webhook.clone())
+            .json(&value)
+            .send()
+            .await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Discord notification POST request failed with code {}",
+                response.status()
+            ))
+        }
+    }
+}
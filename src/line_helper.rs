@@ -53,6 +53,43 @@ fn find_newline(s: &[u8]) -> Option<usize> {
         .find_map(|(idx, c)| if *c == b'\n' { Some(idx) } else { None })
 }
 
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Does `line[idx..]` start a Rust mangled symbol (legacy `_Z`/`_ZN` or v0
+/// `_R`), at a position that isn't itself the middle of a larger identifier?
+fn mangled_symbol_start(line: &str, idx: usize) -> bool {
+    let bytes = line.as_bytes();
+    let starts_mangled = bytes[idx..].starts_with(b"_Z") || bytes[idx..].starts_with(b"_R");
+    let at_word_boundary = idx == 0 || !is_ident_byte(bytes[idx - 1]);
+    starts_mangled && at_word_boundary
+}
+
+/// Scan `line` for Rust mangled symbols and replace each one with its
+/// demangled form, leaving everything else (including unmatched or
+/// already-readable text) untouched.
+pub(crate) fn demangle_line(line: &str) -> String {
+    let bytes = line.as_bytes();
+    let mut out = String::with_capacity(line.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if mangled_symbol_start(line, i) {
+            let mut end = i;
+            while end < bytes.len() && is_ident_byte(bytes[end]) {
+                end += 1;
+            }
+            out.push_str(&rustc_demangle::demangle(&line[i..end]).to_string());
+            i = end;
+        } else {
+            let ch = line[i..].chars().next().expect("i < bytes.len()");
+            out.push(ch);
+            i += ch.len_utf8();
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,4 +135,33 @@ mod tests {
         );
         assert_eq!(helper.finish(), Some("world".to_owned()))
     }
+
+    #[test]
+    fn demangle_line_legacy_symbol() {
+        let line = "thread panicked at _ZN16demangle_fixture6new_v117hf6292f058601f90cE";
+        let demangled = demangle_line(line);
+        assert!(demangled.contains("demangle_fixture::new_v1"));
+        assert!(!demangled.contains("_ZN16demangle_fixture"));
+    }
+
+    #[test]
+    fn demangle_line_v0_symbol() {
+        let line = "_RNvCsj0c1MsKIVNj_16demangle_fixture6new_v1";
+        let demangled = demangle_line(line);
+        assert_ne!(demangled, line);
+    }
+
+    #[test]
+    fn demangle_line_leaves_unmatched_text_alone() {
+        let line = "hello world, nothing mangled here";
+        assert_eq!(demangle_line(line), line);
+    }
+
+    #[test]
+    fn demangle_line_requires_word_boundary() {
+        // "foo_ZN..." is not itself a mangled symbol, it's in the middle of
+        // a larger identifier, so it must not be touched.
+        let line = "foo_ZN4core3fmt9Arguments6new_v117hbeef00000000000E";
+        assert_eq!(demangle_line(line), line);
+    }
 }
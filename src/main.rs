@@ -4,7 +4,9 @@ use clap::Parser;
 use pid1::Pid1Settings;
 
 mod cli;
+mod discord;
 mod line_helper;
+mod notifier;
 mod slack;
 
 fn main() -> Result<()> {
@@ -14,5 +16,7 @@ fn main() -> Result<()> {
         .context("pid1: Child process launch failed")?;
 
     let cli = cli::Cli::parse();
-    cli.run()
+    tokio::runtime::Runtime::new()
+        .context("Unable to start tokio runtime")?
+        .block_on(cli.run())
 }
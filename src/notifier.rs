@@ -0,0 +1,35 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Application context attached to every outgoing notification, independent
+/// of which sink ends up delivering it.
+#[derive(Clone)]
+pub(crate) struct AppDetail {
+    pub(crate) message: String,
+    pub(crate) description: String,
+    pub(crate) version: String,
+    pub(crate) image_url: Option<String>,
+}
+
+/// A sink that can be alerted when the supervised process appears unhealthy.
+///
+/// Implementations are expected to format `err` and `recent_output` however
+/// best suits their destination; a failure to deliver is returned rather
+/// than panicking so that callers can fan a single notification out to
+/// multiple sinks without one failure masking the others. Delivery is async,
+/// so the output readers, watchdogs and signal forwarding keep running while
+/// a notification is in flight; the caller still awaits each delivery, and
+/// no timeout is applied to it.
+#[async_trait]
+pub(crate) trait Notifier: Send + Sync {
+    async fn send_notification(&self, err: &anyhow::Error, recent_output: &str) -> Result<()>;
+}
+
+/// Strip a Docker-style image reference down to just the trailing tag/digest
+/// so long registry paths don't dominate the notification.
+pub(crate) fn readable_image_id(version: &str) -> &str {
+    match version.split(':').last() {
+        Some(last) => last,
+        None => version,
+    }
+}
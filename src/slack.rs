@@ -1,25 +1,14 @@
 use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
 use reqwest::Url;
 
+use crate::notifier::{readable_image_id, AppDetail, Notifier};
+
 pub(crate) struct SlackApp {
     webhook: Url,
     app_info: AppDetail,
 }
 
-pub(crate) struct AppDetail {
-    pub(crate) message: String,
-    pub(crate) description: String,
-    pub(crate) version: String,
-    pub(crate) image_url: Option<String>,
-}
-
-fn readable_image_id(version: &str) -> &str {
-    match version.split(':').last() {
-        Some(last) => last,
-        None => version,
-    }
-}
-
 impl SlackApp {
     pub(crate) fn new(
         webhook: Url,
@@ -48,8 +37,11 @@ impl SlackApp {
             message, self.app_info.description, version
         )
     }
+}
 
-    pub(crate) fn send_notification(&self, message: &anyhow::Error) -> Result<()> {
+#[async_trait]
+impl Notifier for SlackApp {
+    async fn send_notification(&self, err: &anyhow::Error, recent_output: &str) -> Result<()> {
         let description = self.compute_description();
         let mut value = serde_json::json!(
         {
@@ -59,7 +51,7 @@ impl SlackApp {
                     "type": "header",
                     "text": {
                         "type": "plain_text",
-                        "text": message.to_string(),
+                        "text": err.to_string(),
                     }
                 },
                 {
@@ -93,8 +85,30 @@ impl SlackApp {
                 ),
             );
         }
-        let client = reqwest::blocking::Client::new();
-        let response = client.post(self.webhook.clone()).json(&value).send()?;
+        if !recent_output.is_empty() {
+            let object = value
+                .as_object_mut()
+                .context("JSON value should be an object")?;
+            let blocks = object["blocks"]
+                .as_array_mut()
+                .context("Blocks field should be an array")?;
+            blocks.push(serde_json::json!(
+                {
+                    "type": "section",
+                    "block_id": "recent_output",
+                    "text": {
+                        "type": "mrkdwn",
+                        "text": format!("```{recent_output}```")
+                    },
+                }
+            ));
+        }
+        let client = reqwest::Client::new();
+        let response = client
+            .post(self.webhook.clone())
+            .json(&value)
+            .send()
+            .await?;
         if response.status().is_success() {
             Ok(())
         } else {
@@ -108,7 +122,7 @@ impl SlackApp {
 
 #[cfg(test)]
 mod tests {
-    use crate::slack::readable_image_id;
+    use crate::notifier::readable_image_id;
 
     #[test]
     fn guess_readable_image_id_works() {